@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
 
 /// Program ID of the smart contract.
 /// Must match the programId used in the C# client.
@@ -19,28 +20,70 @@ pub mod voting {
     /// - company_id: company identifier
     /// - voting_id: voting identifier
     /// - question: voting question text
-    /// - options: list of answer options (2 or 3)
+    /// - options: list of answer options (2..=MAX_OPTIONS)
+    /// - max_lockup_secs: lockup duration (in seconds) that yields the full weight bonus
+    /// - lockup_multiplier: bonus factor applied to fully locked deposits
+    /// - start_ts: Unix timestamp before which votes are rejected (0 = unbounded)
+    /// - end_ts: Unix timestamp after which votes are rejected (0 = unbounded)
+    /// - governance_mint: the SPL mint that deposited governance tokens must belong to
     pub fn initialize_voting(
         ctx: Context<InitializeVoting>,
         company_id: u64,
         voting_id: u64,
         question: String,
         options: Vec<String>,
+        max_lockup_secs: i64,
+        lockup_multiplier: u64,
+        start_ts: i64,
+        end_ts: i64,
+        governance_mint: Pubkey,
     ) -> Result<()> {
         // Validate number of options
         require!(
-            options.len() >= 2 && options.len() <= 3,
+            options.len() >= 2 && options.len() <= MAX_OPTIONS,
             VotingError::InvalidOptionsCount
         );
 
+        // Cap per-option and question byte lengths to bound rent and guard abuse
+        require!(
+            question.len() <= MAX_QUESTION_LEN,
+            VotingError::QuestionTooLong
+        );
+        require!(
+            options.iter().all(|o| o.len() <= MAX_OPTION_LEN),
+            VotingError::OptionTooLong
+        );
+
+        // A bounded window must actually be able to accept a ballot
+        require!(
+            start_ts == 0 || end_ts == 0 || start_ts <= end_ts,
+            VotingError::InvalidVotingWindow
+        );
+
         let voting_account = &mut ctx.accounts.voting;
 
+        // Stamp the current layout version and record the creating authority.
+        voting_account.version = CURRENT_VERSION;
+        voting_account.authority = ctx.accounts.authority.key();
+
         // Store voting metadata
         voting_account.company_id = company_id;
         voting_account.voting_id = voting_id;
         voting_account.question = question;
         voting_account.options = options;
 
+        // Store voter-weight configuration
+        voting_account.max_lockup_secs = max_lockup_secs;
+        voting_account.lockup_multiplier = lockup_multiplier;
+        voting_account.governance_mint = governance_mint;
+
+        // Store the voting window (0 means that bound is unbounded)
+        voting_account.start_ts = start_ts;
+        voting_account.end_ts = end_ts;
+
+        // Results are mutable until the authority finalizes
+        voting_account.finalized = false;
+
         // Initialize vote counters
         voting_account.votes = vec![0; voting_account.options.len()];
         voting_account.total_votes = 0;
@@ -48,11 +91,127 @@ pub mod voting {
         Ok(())
     }
 
+    /// Registers a voter by snapshotting their staked governance tokens.
+    ///
+    /// Creates a VoterWeightRecord (PDA) tied to the voting and the voter wallet.
+    /// The deposited amount is read from the voter's SPL token account and then
+    /// moved into a program-owned `escrow` token account for the duration of the
+    /// lockup, so the deposit actually backing `lockup_end_ts` cannot be
+    /// transferred away the moment the record is created. The token account must
+    /// belong to the voting's `governance_mint`, otherwise a self-minted token
+    /// could claim arbitrary weight. Only allowed while the voting window is
+    /// open and results are not finalized, same as `vote`.
+    ///
+    /// Parameters:
+    /// - company_id: company identifier (used for PDA derivation)
+    /// - voting_id: voting identifier (used for PDA derivation)
+    /// - lockup_end_ts: Unix timestamp at which the deposit lockup ends
+    pub fn register_voter(
+        ctx: Context<RegisterVoter>,
+        _company_id: u64,
+        _voting_id: u64,
+        lockup_end_ts: i64,
+    ) -> Result<()> {
+        // Only while the window is open and results are not finalized; otherwise
+        // a voter could lock tokens into escrow with no way to ever cast them.
+        let voting = &ctx.accounts.voting;
+        require!(!voting.finalized, VotingError::VotingClosed);
+        let now = Clock::get()?.unix_timestamp;
+        voting.require_open(now)?;
+
+        let deposited_amount = ctx.accounts.token_account.amount;
+
+        // A voter with no stake has no voting power
+        require!(deposited_amount > 0, VotingError::NoVotingPower);
+
+        // Lock the deposit in escrow; only `reclaim_deposit` can move it back out,
+        // and only once `lockup_end_ts` has passed.
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.token_account.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                    authority: ctx.accounts.voter.to_account_info(),
+                },
+            ),
+            deposited_amount,
+        )?;
+
+        let record = &mut ctx.accounts.voter_weight;
+        record.voter = ctx.accounts.voter.key();
+        record.deposited_amount = deposited_amount;
+        record.lockup_end_ts = lockup_end_ts;
+
+        Ok(())
+    }
+
+    /// Returns an expired deposit from escrow to the voter and reclaims its rent.
+    ///
+    /// Only callable once `lockup_end_ts` has passed, mirroring the check
+    /// `vote_weight` already applies to cap the bonus at zero past that point.
+    /// The record's PDA is the escrow's token authority and signs the release;
+    /// the `VoterWeightRecord` is then closed (`close = voter`), so it can no
+    /// longer back a vote.
+    ///
+    /// Parameters:
+    /// - company_id: company identifier (used for PDA derivation)
+    /// - voting_id: voting identifier (used for PDA derivation)
+    pub fn reclaim_deposit(
+        ctx: Context<ReclaimDeposit>,
+        company_id: u64,
+        voting_id: u64,
+    ) -> Result<()> {
+        let record = &ctx.accounts.voter_weight;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= record.lockup_end_ts, VotingError::LockupNotExpired);
+
+        let voter_key = ctx.accounts.voter.key();
+        let bump = ctx.bumps.voter_weight;
+        let signer_seeds: &[&[u8]] = &[
+            b"voter",
+            company_id.to_le_bytes().as_ref(),
+            voting_id.to_le_bytes().as_ref(),
+            voter_key.as_ref(),
+            &[bump],
+        ];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.escrow.to_account_info(),
+                    to: ctx.accounts.token_account.to_account_info(),
+                    authority: ctx.accounts.voter_weight.to_account_info(),
+                },
+                &[signer_seeds],
+            ),
+            ctx.accounts.escrow.amount,
+        )?;
+
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow.to_account_info(),
+                destination: ctx.accounts.voter.to_account_info(),
+                authority: ctx.accounts.voter_weight.to_account_info(),
+            },
+            &[signer_seeds],
+        ))?;
+
+        Ok(())
+    }
+
     /// Casts a vote for a specific option.
     ///
     /// Creates a VoteAccount (PDA) tied to the voting and the voter wallet.
     /// This guarantees that each user can vote only once.
     ///
+    /// The added weight reflects the voter's staked tokens and remaining lockup,
+    /// read from the VoterWeightRecord and verified against the escrow account
+    /// the deposit was locked into by `register_voter`.
+    ///
     /// Parameters:
     /// - company_id: company identifier (used for PDA derivation)
     /// - voting_id: voting identifier (used for PDA derivation)
@@ -71,14 +230,267 @@ pub mod voting {
             VotingError::InvalidOption
         );
 
+        // Verify the locked deposit still matches the escrowed balance
+        let record = &ctx.accounts.voter_weight;
+        require!(
+            ctx.accounts.escrow.amount >= record.deposited_amount,
+            VotingError::NoVotingPower
+        );
+        require!(record.deposited_amount > 0, VotingError::NoVotingPower);
+
+        // Enforce the voting window and finalization, if configured
+        require!(!voting.finalized, VotingError::VotingClosed);
+        let now = Clock::get()?.unix_timestamp;
+        voting.require_open(now)?;
+
+        // Compute the governance weight for this ballot
+        let weight = voting.vote_weight(record.deposited_amount, record.lockup_end_ts, now)?;
+
         // Store user's vote
         let vote_account = &mut ctx.accounts.vote;
         vote_account.voter = ctx.accounts.voter.key();
         vote_account.selected_option = selected_option;
+        vote_account.weight = weight;
 
         // Update voting results
-        voting.votes[selected_option as usize] += 1;
-        voting.total_votes += 1;
+        voting.votes[selected_option as usize] += weight;
+        voting.total_votes += weight;
+
+        Ok(())
+    }
+
+    /// Creates the singleton `ProgramConfig` (PDA, seeds = ["config"]) and sets
+    /// its initial `migration_admin`. Callable exactly once, since `init` fails
+    /// if the PDA already exists; run this as part of deployment, before any
+    /// V0 `VotingAccount` needs migrating.
+    ///
+    /// Parameters:
+    /// - migration_admin: pubkey authorized to migrate pre-versioning (V0) accounts
+    pub fn initialize_config(ctx: Context<InitializeConfig>, migration_admin: Pubkey) -> Result<()> {
+        ctx.accounts.config.migration_admin = migration_admin;
+        Ok(())
+    }
+
+    /// Rotates `ProgramConfig::migration_admin`. Only the current admin may do this.
+    ///
+    /// Parameters:
+    /// - new_admin: pubkey to become the new migration admin
+    pub fn set_migration_admin(ctx: Context<SetMigrationAdmin>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.config.migration_admin = new_admin;
+        Ok(())
+    }
+
+    /// Migrates a VotingAccount from an older on-chain layout to the current one.
+    ///
+    /// Reads the account through the `VotingAccountVersions` wrapper, reallocs it
+    /// to the size `VotingAccount::space_for` gives for its contents, copies the existing `question`/`options`/`votes`/
+    /// `total_votes` and fills newly added fields with defaults, then bumps
+    /// `version`. Migrating an account that is already current is a no-op.
+    ///
+    /// For a current-layout account, only the stored `authority` may call
+    /// this. Accounts predating the `authority` field (V0) have no stored
+    /// authority to check against, so migrating one is instead gated behind
+    /// `ProgramConfig::migration_admin`; the caller becomes the migrated
+    /// account's recorded `authority`.
+    ///
+    /// Parameters:
+    /// - company_id: company identifier (used for PDA derivation)
+    /// - voting_id: voting identifier (used for PDA derivation)
+    pub fn migrate_voting(
+        ctx: Context<MigrateVoting>,
+        _company_id: u64,
+        _voting_id: u64,
+    ) -> Result<()> {
+        let info = ctx.accounts.voting.to_account_info();
+
+        // Load whichever layout is currently on-chain.
+        let current = match VotingAccount::load_versioned(&info)? {
+            VotingAccountVersions::V1(v) => {
+                // Already current: only the authority may poke it, but nothing changes.
+                require_keys_eq!(
+                    v.authority,
+                    ctx.accounts.authority.key(),
+                    VotingError::Unauthorized
+                );
+                return Ok(());
+            }
+            VotingAccountVersions::V0(old) => {
+                // V0 has no stored authority to check the caller against, so
+                // this is gated behind the rotatable config admin rather than
+                // being handed to whichever caller gets there first.
+                require_keys_eq!(
+                    ctx.accounts.authority.key(),
+                    ctx.accounts.config.migration_admin,
+                    VotingError::Unauthorized
+                );
+                VotingAccount {
+                    version: CURRENT_VERSION,
+                    authority: ctx.accounts.authority.key(),
+                    company_id: old.company_id,
+                    voting_id: old.voting_id,
+                    question: old.question,
+                    options: old.options,
+                    votes: old.votes,
+                    total_votes: old.total_votes,
+                    max_lockup_secs: 0,
+                    lockup_multiplier: 0,
+                    start_ts: 0,
+                    end_ts: 0,
+                    finalized: false,
+                    // V0 predates token-weighted voting, so there is no real mint
+                    // to carry over; register_voter/vote will reject every token
+                    // account until the authority recreates the voting with one.
+                    governance_mint: Pubkey::default(),
+                }
+            }
+        };
+
+        // Grow the account to fit the current layout for its actual contents.
+        let new_space = VotingAccount::space_for(current.question.len(), &current.options);
+        let rent = Rent::get()?;
+        let min_balance = rent.minimum_balance(new_space);
+        let lamports = info.lamports();
+        if min_balance > lamports {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                min_balance - lamports,
+            )?;
+        }
+        info.realloc(new_space, false)?;
+
+        // Persist the upgraded layout.
+        let mut data = info.try_borrow_mut_data()?;
+        current.try_serialize(&mut data.as_mut())?;
+
+        Ok(())
+    }
+
+    /// Retracts a previously cast ballot and refunds its rent.
+    ///
+    /// While the voting window is still open, removes the ballot's weight from
+    /// the tallies and closes the VoteAccount (`close = voter`), returning the
+    /// rent lamports to the voter.
+    ///
+    /// Parameters:
+    /// - company_id: company identifier (used for PDA derivation)
+    /// - voting_id: voting identifier (used for PDA derivation)
+    pub fn retract_vote(
+        ctx: Context<RetractVote>,
+        _company_id: u64,
+        _voting_id: u64,
+    ) -> Result<()> {
+        let voting = &mut ctx.accounts.voting;
+
+        // Only while the window is open and results are not finalized
+        require!(!voting.finalized, VotingError::VotingClosed);
+        let now = Clock::get()?.unix_timestamp;
+        voting.require_open(now)?;
+
+        let vote = &ctx.accounts.vote;
+        let idx = vote.selected_option as usize;
+
+        // Remove this ballot's weight, guarding against underflow
+        voting.votes[idx] = voting.votes[idx]
+            .checked_sub(vote.weight)
+            .ok_or(VotingError::VoteUnderflow)?;
+        voting.total_votes = voting
+            .total_votes
+            .checked_sub(vote.weight)
+            .ok_or(VotingError::VoteUnderflow)?;
+
+        Ok(())
+    }
+
+    /// Moves an existing ballot from its current option to a new one.
+    ///
+    /// Unlike `retract_vote` the VoteAccount stays open; the ballot's weight is
+    /// subtracted from the old option and added to the new one atomically.
+    ///
+    /// Parameters:
+    /// - company_id: company identifier (used for PDA derivation)
+    /// - voting_id: voting identifier (used for PDA derivation)
+    /// - new_option: index of the option to move the ballot to
+    pub fn change_vote(
+        ctx: Context<ChangeVote>,
+        _company_id: u64,
+        _voting_id: u64,
+        new_option: u8,
+    ) -> Result<()> {
+        let voting = &mut ctx.accounts.voting;
+
+        // Only while the window is open and results are not finalized
+        require!(!voting.finalized, VotingError::VotingClosed);
+        let now = Clock::get()?.unix_timestamp;
+        voting.require_open(now)?;
+
+        // Ensure the target option exists
+        require!(
+            (new_option as usize) < voting.options.len(),
+            VotingError::InvalidOption
+        );
+
+        let vote = &mut ctx.accounts.vote;
+        let old_idx = vote.selected_option as usize;
+        let new_idx = new_option as usize;
+        if old_idx == new_idx {
+            return Ok(());
+        }
+
+        // Move the weight from the old option to the new one
+        voting.votes[old_idx] = voting.votes[old_idx]
+            .checked_sub(vote.weight)
+            .ok_or(VotingError::VoteUnderflow)?;
+        voting.votes[new_idx] += vote.weight;
+
+        vote.selected_option = new_option;
+
+        Ok(())
+    }
+
+    /// Finalizes a voting, freezing its results and emitting a tally snapshot.
+    ///
+    /// Callable only by the stored `authority`. Sets the `finalized` flag so any
+    /// subsequent `vote`/`change_vote`/`retract_vote` is rejected with
+    /// `VotingError::VotingClosed`, and emits a `VotingFinalized` event with the
+    /// final counters and the winning option index computed on-chain, letting
+    /// clients subscribe to logs instead of polling account state.
+    pub fn finalize_voting(
+        ctx: Context<FinalizeVoting>,
+        _company_id: u64,
+        _voting_id: u64,
+    ) -> Result<()> {
+        let voting = &mut ctx.accounts.voting;
+        voting.finalized = true;
+
+        // Winning option is the lowest index holding the maximum vote weight.
+        // `max_by_key` keeps the *last* maximal element on ties, so fold
+        // manually with a strict `>` to keep the first (lowest-index) one.
+        let winning_option = voting
+            .votes
+            .iter()
+            .enumerate()
+            .fold((0usize, 0u64), |(best_idx, best_count), (idx, &count)| {
+                if count > best_count {
+                    (idx, count)
+                } else {
+                    (best_idx, best_count)
+                }
+            })
+            .0 as u8;
+
+        emit!(VotingFinalized {
+            company_id: voting.company_id,
+            voting_id: voting.voting_id,
+            votes: voting.votes.clone(),
+            total_votes: voting.total_votes,
+            winning_option,
+        });
 
         Ok(())
     }
@@ -89,17 +501,18 @@ pub mod voting {
 /// Creates a VotingAccount PDA using:
 /// seeds = ["voting", company_id, voting_id]
 #[derive(Accounts)]
-#[instruction(company_id: u64, voting_id: u64)]
+#[instruction(company_id: u64, voting_id: u64, question: String, options: Vec<String>)]
 pub struct InitializeVoting<'info> {
     /// Voting account (PDA).
     /// Created once per company + voting.
+    /// Space is computed from the actual question and option lengths.
     #[account(
         init,
         payer = authority,
-        space = VotingAccount::SPACE,
+        space = VotingAccount::space_for(question.len(), &options),
         seeds = [
-            b"voting", 
-            company_id.to_le_bytes().as_ref(), 
+            b"voting",
+            company_id.to_le_bytes().as_ref(),
             voting_id.to_le_bytes().as_ref()
         ],
         bump
@@ -114,6 +527,79 @@ pub struct InitializeVoting<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Accounts context for registering a voter's staked weight.
+///
+/// Creates a VoterWeightRecord PDA using:
+/// seeds = ["voter", company_id, voting_id, voter]
+#[derive(Accounts)]
+#[instruction(company_id: u64, voting_id: u64)]
+pub struct RegisterVoter<'info> {
+    /// Existing voting account, used to confirm the required governance mint.
+    #[account(
+        seeds = [
+            b"voting",
+            company_id.to_le_bytes().as_ref(),
+            voting_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub voting: Account<'info, VotingAccount>,
+
+    /// Voter-weight record (PDA).
+    /// One snapshot per voter per voting.
+    #[account(
+        init,
+        payer = voter,
+        space = VoterWeightRecord::SPACE,
+        seeds = [
+            b"voter",
+            company_id.to_le_bytes().as_ref(),
+            voting_id.to_le_bytes().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub voter_weight: Account<'info, VoterWeightRecord>,
+
+    /// SPL token account holding the voter's deposited governance tokens.
+    /// Must belong to the voting's `governance_mint`, otherwise a voter could
+    /// mint themselves an arbitrary supply of a throwaway token.
+    #[account(
+        mut,
+        constraint = token_account.owner == voter.key(),
+        constraint = token_account.mint == voting.governance_mint @ VotingError::WrongGovernanceMint
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// Escrow holding the deposit for the duration of the lockup. Its authority
+    /// is the `voter_weight` PDA itself, so only this program can move tokens
+    /// out of it, and only via `reclaim_deposit` once the lockup has expired.
+    #[account(
+        init,
+        payer = voter,
+        token::mint = token_account.mint,
+        token::authority = voter_weight,
+        seeds = [
+            b"escrow",
+            company_id.to_le_bytes().as_ref(),
+            voting_id.to_le_bytes().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    /// Voter registering their stake
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    /// SPL token program (required to move the deposit into escrow)
+    pub token_program: Program<'info, Token>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
 /// Accounts context for voting.
 ///
 /// Uses an existing VotingAccount and creates a VoteAccount PDA
@@ -125,14 +611,41 @@ pub struct Vote<'info> {
     #[account(
         mut,
         seeds = [
-            b"voting", 
-            company_id.to_le_bytes().as_ref(), 
+            b"voting",
+            company_id.to_le_bytes().as_ref(),
             voting_id.to_le_bytes().as_ref()
         ],
         bump
     )]
     pub voting: Account<'info, VotingAccount>,
 
+    /// Voter-weight record snapshotted by `register_voter`.
+    #[account(
+        seeds = [
+            b"voter",
+            company_id.to_le_bytes().as_ref(),
+            voting_id.to_le_bytes().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump,
+        has_one = voter
+    )]
+    pub voter_weight: Account<'info, VoterWeightRecord>,
+
+    /// Escrow created by `register_voter`; verifies the locked deposit is still
+    /// intact (it can only have left via `reclaim_deposit`, which would have
+    /// closed `voter_weight`, so the account above would fail to load).
+    #[account(
+        seeds = [
+            b"escrow",
+            company_id.to_le_bytes().as_ref(),
+            voting_id.to_le_bytes().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
     /// Vote account (PDA).
     /// Ensures one vote per user.
     #[account(
@@ -152,32 +665,380 @@ pub struct Vote<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Accounts context for reclaiming an expired deposit from escrow.
+///
+/// Closes the `VoterWeightRecord` (`close = voter`) and the `escrow` token
+/// account, returning both the deposit and the rent to the voter.
+#[derive(Accounts)]
+#[instruction(company_id: u64, voting_id: u64)]
+pub struct ReclaimDeposit<'info> {
+    /// Voter-weight record being reclaimed; its PDA signs for the escrow, and it
+    /// is closed once the deposit is released.
+    #[account(
+        mut,
+        close = voter,
+        seeds = [
+            b"voter",
+            company_id.to_le_bytes().as_ref(),
+            voting_id.to_le_bytes().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump,
+        has_one = voter
+    )]
+    pub voter_weight: Account<'info, VoterWeightRecord>,
+
+    /// Escrow the deposit is released from.
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            company_id.to_le_bytes().as_ref(),
+            voting_id.to_le_bytes().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    /// Destination for the reclaimed tokens.
+    #[account(mut, constraint = token_account.owner == voter.key())]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// Voter reclaiming their deposit
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Accounts context for retracting a vote.
+///
+/// Uses the existing VotingAccount and the voter's VoteAccount, which is
+/// closed on success to refund its rent.
+#[derive(Accounts)]
+#[instruction(company_id: u64, voting_id: u64)]
+pub struct RetractVote<'info> {
+    /// Existing voting account
+    #[account(
+        mut,
+        seeds = [
+            b"voting",
+            company_id.to_le_bytes().as_ref(),
+            voting_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub voting: Account<'info, VotingAccount>,
+
+    /// Vote account (PDA), closed back to the voter on retraction.
+    #[account(
+        mut,
+        close = voter,
+        seeds = [b"vote", voting.key().as_ref(), voter.key().as_ref()],
+        bump,
+        has_one = voter
+    )]
+    pub vote: Account<'info, VoteAccount>,
+
+    /// User retracting the vote
+    #[account(mut)]
+    pub voter: Signer<'info>,
+}
+
+/// Accounts context for changing a vote.
+///
+/// Uses the existing VotingAccount and the voter's VoteAccount, which stays
+/// open and is updated in place.
+#[derive(Accounts)]
+#[instruction(company_id: u64, voting_id: u64)]
+pub struct ChangeVote<'info> {
+    /// Existing voting account
+    #[account(
+        mut,
+        seeds = [
+            b"voting",
+            company_id.to_le_bytes().as_ref(),
+            voting_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub voting: Account<'info, VotingAccount>,
+
+    /// Vote account (PDA) updated to the new option.
+    #[account(
+        mut,
+        seeds = [b"vote", voting.key().as_ref(), voter.key().as_ref()],
+        bump,
+        has_one = voter
+    )]
+    pub vote: Account<'info, VoteAccount>,
+
+    /// User changing the vote
+    #[account(mut)]
+    pub voter: Signer<'info>,
+}
+
+/// Accounts context for finalizing a voting.
+///
+/// Only the stored `authority` may finalize.
+#[derive(Accounts)]
+#[instruction(company_id: u64, voting_id: u64)]
+pub struct FinalizeVoting<'info> {
+    /// Existing voting account
+    #[account(
+        mut,
+        seeds = [
+            b"voting",
+            company_id.to_le_bytes().as_ref(),
+            voting_id.to_le_bytes().as_ref()
+        ],
+        bump,
+        has_one = authority @ VotingError::Unauthorized
+    )]
+    pub voting: Account<'info, VotingAccount>,
+
+    /// Voting authority
+    pub authority: Signer<'info>,
+}
+
+/// Accounts context for migrating a voting to the current layout.
+///
+/// The account is taken as a raw `AccountInfo` because an old-layout account
+/// would fail the typed `Account<VotingAccount>` deserialization; the handler
+/// deserializes it through `VotingAccountVersions` instead. The `seeds`/`bump`
+/// and `owner` constraints still pin it to the exact VotingAccount PDA for
+/// this company/voting, so a `VoteAccount`, a `VoterWeightRecord`, or another
+/// company's VotingAccount can never be substituted in.
+#[derive(Accounts)]
+#[instruction(company_id: u64, voting_id: u64)]
+pub struct MigrateVoting<'info> {
+    /// CHECK: deserialized manually through the `VotingAccountVersions` wrapper;
+    /// address and ownership are validated by the constraints below.
+    #[account(
+        mut,
+        seeds = [
+            b"voting",
+            company_id.to_le_bytes().as_ref(),
+            voting_id.to_le_bytes().as_ref()
+        ],
+        bump,
+        owner = crate::ID
+    )]
+    pub voting: AccountInfo<'info>,
+
+    /// Program configuration; `migration_admin` gates migrating V0 accounts.
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Authority paying for the realloc; becomes the recorded authority for V0 accounts.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program (required for the rent top-up transfer)
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts context for creating the singleton `ProgramConfig`.
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    /// Program configuration (PDA). `init` ensures this only succeeds once.
+    #[account(
+        init,
+        payer = payer,
+        space = ProgramConfig::SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Account paying for PDA creation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program (required for account initialization)
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts context for rotating `ProgramConfig::migration_admin`.
+#[derive(Accounts)]
+pub struct SetMigrationAdmin<'info> {
+    /// Program configuration (PDA).
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        has_one = migration_admin @ VotingError::Unauthorized
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Current migration admin
+    pub migration_admin: Signer<'info>,
+}
+
+/// Current `VotingAccount` layout version.
+pub const CURRENT_VERSION: u8 = 1;
+
+/// Maximum number of answer options a voting may have.
+pub const MAX_OPTIONS: usize = 10;
+
+/// Maximum byte length of a single answer option.
+pub const MAX_OPTION_LEN: usize = 64;
+
+/// Maximum byte length of the question text.
+pub const MAX_QUESTION_LEN: usize = 256;
+
 /// Main voting account.
 /// Stores voting configuration and results.
 #[account]
 pub struct VotingAccount {
+    pub version: u8,
+    pub authority: Pubkey,
     pub company_id: u64,
     pub voting_id: u64,
     pub question: String,
     pub options: Vec<String>,
     pub votes: Vec<u64>,
     pub total_votes: u64,
+    pub max_lockup_secs: i64,
+    pub lockup_multiplier: u64,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub finalized: bool,
+    pub governance_mint: Pubkey,
+}
+
+/// Original (pre-versioning) on-chain layout of `VotingAccount`.
+///
+/// Retained only so `migrate_voting` can read accounts created before the
+/// `version`/`authority` and voter-weight fields existed.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VotingAccountV0 {
+    pub company_id: u64,
+    pub voting_id: u64,
+    pub question: String,
+    pub options: Vec<String>,
+    pub votes: Vec<u64>,
+    pub total_votes: u64,
+}
+
+/// Version wrapper returned by `VotingAccount::load_versioned`, which
+/// `migrate_voting` uses to load accounts on either layout. The other
+/// instructions (`vote` included) load only the current layout through
+/// `Account<VotingAccount>`; they do not read V0 accounts.
+pub enum VotingAccountVersions {
+    /// Original pre-versioning layout.
+    V0(VotingAccountV0),
+    /// Current layout.
+    V1(VotingAccount),
 }
 
 impl VotingAccount {
-    /// Reserved account size (in bytes).
-    ///
-    /// calculated for:
-    /// - question up to ~256 bytes
-    /// - up to 3 options, ~64 bytes each
-    pub const SPACE: usize =
+    /// Fixed (content-independent) overhead of the account, in bytes.
+    const FIXED: usize =
         8 + // discriminator
+        1 + // version
+        32 + // authority
         8 + // company_id
         8 + // voting_id
-        4 + 256 + // question
-        4 + (3 * (4 + 64)) + // options
-        4 + (3 * 8) + // votes
-        8; // total_votes
+        8 + // total_votes
+        8 + // max_lockup_secs
+        8 + // lockup_multiplier
+        8 + // start_ts
+        8 + // end_ts
+        1 + // finalized
+        32; // governance_mint
+
+    /// Exact account size needed for the given question and option lengths.
+    ///
+    /// Includes the 4-byte Borsh length prefix for the question, options vector,
+    /// each option string, and the votes vector (one `u64` per option).
+    pub fn space_for(question_len: usize, options: &[String]) -> usize {
+        let options_bytes: usize = options.iter().map(|o| 4 + o.len()).sum();
+        Self::FIXED
+            + (4 + question_len) // question
+            + (4 + options_bytes) // options
+            + (4 + options.len() * 8) // votes
+    }
+
+    /// Computes the governance weight of a deposit.
+    ///
+    /// `weight = deposited * (1 + lockup_multiplier * remaining / max_lockup_secs)`,
+    /// where `remaining` is the lockup time left, clamped to `[0, max_lockup_secs]`.
+    /// Expired lockups therefore contribute only the base `deposited` amount.
+    ///
+    /// `lockup_multiplier` is authority-supplied and unbounded, so the
+    /// intermediate product can overflow even `u128` for large-but-plausible
+    /// configs; every step is `checked_*` and surfaces `VotingError::WeightOverflow`
+    /// instead of panicking the transaction. The remaining-time subtraction
+    /// saturates, so an out-of-range `lockup_end_ts` is simply clamped.
+    pub fn vote_weight(&self, deposited: u64, lockup_end_ts: i64, now: i64) -> Result<u64> {
+        if self.max_lockup_secs <= 0 || self.lockup_multiplier == 0 {
+            return Ok(deposited);
+        }
+
+        let remaining = lockup_end_ts.saturating_sub(now).clamp(0, self.max_lockup_secs);
+
+        let bonus: u128 = (deposited as u128)
+            .checked_mul(self.lockup_multiplier as u128)
+            .and_then(|v| v.checked_mul(remaining as u128))
+            .and_then(|v| v.checked_div(self.max_lockup_secs as u128))
+            .ok_or(VotingError::WeightOverflow)?;
+        let bonus: u64 = bonus.try_into().map_err(|_| VotingError::WeightOverflow)?;
+
+        Ok(deposited
+            .checked_add(bonus)
+            .ok_or(VotingError::WeightOverflow)?)
+    }
+
+    /// Ensures `now` falls within the configured voting window.
+    ///
+    /// A bound of `0` is treated as unbounded, so a voting with both bounds at
+    /// `0` accepts ballots forever (the original always-open behavior).
+    pub fn require_open(&self, now: i64) -> Result<()> {
+        require!(
+            self.start_ts == 0 || now >= self.start_ts,
+            VotingError::VotingNotStarted
+        );
+        require!(
+            self.end_ts == 0 || now <= self.end_ts,
+            VotingError::VotingClosed
+        );
+        Ok(())
+    }
+
+    /// Deserializes an account through the version wrapper.
+    ///
+    /// The account's 8-byte discriminator is identical for both layouts (the
+    /// type's name never changed), so it cannot tell them apart, and a V0
+    /// account has no `version` field to read in the first place — its first
+    /// data byte is just the low byte of `company_id`, which is `1` for plenty
+    /// of real companies. Instead, try the current layout and accept it only
+    /// if it deserializes cleanly *and* consumes every remaining byte; a real
+    /// V0 account, lacking the `version`/`authority` prefix, either fails to
+    /// parse as V1 or leaves bytes over. Only then is it parsed as V0; since
+    /// V0 accounts were allocated at a fixed size, any bytes left over after
+    /// the V0 fields must be zero padding.
+    pub fn load_versioned(info: &AccountInfo) -> Result<VotingAccountVersions> {
+        let data = info.try_borrow_data()?;
+        require!(data.len() >= 8, VotingError::InvalidAccount);
+
+        let mut remaining = &data[8..];
+        if let Ok(current) = VotingAccount::deserialize(&mut remaining) {
+            if remaining.is_empty() {
+                return Ok(VotingAccountVersions::V1(current));
+            }
+        }
+
+        let mut remaining = &data[8..];
+        let old = VotingAccountV0::deserialize(&mut remaining)?;
+        require!(
+            remaining.iter().all(|&b| b == 0),
+            VotingError::InvalidAccount
+        );
+        Ok(VotingAccountVersions::V0(old))
+    }
 }
 
 /// User vote account.
@@ -186,6 +1047,7 @@ impl VotingAccount {
 pub struct VoteAccount {
     pub voter: Pubkey,
     pub selected_option: u8,
+    pub weight: u64,
 }
 
 impl VoteAccount {
@@ -193,17 +1055,115 @@ impl VoteAccount {
     pub const SPACE: usize =
         8 + // discriminator
         32 + // voter pubkey
-        1; // selected_option
+        1 + // selected_option
+        8; // weight
+}
+
+/// Snapshot of a voter's staked governance tokens for a voting.
+/// Created by `register_voter` and read when casting a ballot.
+#[account]
+pub struct VoterWeightRecord {
+    pub voter: Pubkey,
+    pub deposited_amount: u64,
+    pub lockup_end_ts: i64,
+}
+
+impl VoterWeightRecord {
+    /// Reserved account size
+    pub const SPACE: usize =
+        8 + // discriminator
+        32 + // voter pubkey
+        8 + // deposited_amount
+        8; // lockup_end_ts
+}
+
+/// Singleton program configuration (PDA, seeds = ["config"]).
+///
+/// Holds the `migration_admin` used to gate migrating pre-versioning (V0)
+/// `VotingAccount`s, since V0 predates the `authority` field and so has no
+/// stored owner to check a caller against. Created once via
+/// `initialize_config` and rotatable afterwards via `set_migration_admin`.
+#[account]
+pub struct ProgramConfig {
+    pub migration_admin: Pubkey,
+}
+
+impl ProgramConfig {
+    /// Reserved account size
+    pub const SPACE: usize =
+        8 + // discriminator
+        32; // migration_admin
+}
+
+/// Emitted when a voting is finalized.
+///
+/// Lets clients (the C# client, off-chain indexers) react to a canonical
+/// "poll is final" signal via logs instead of polling mutable counters.
+#[event]
+pub struct VotingFinalized {
+    pub company_id: u64,
+    pub voting_id: u64,
+    pub votes: Vec<u64>,
+    pub total_votes: u64,
+    pub winning_option: u8,
 }
 
 /// Custom program errors
 #[error_code]
 pub enum VotingError {
     /// Invalid number of answer options
-    #[msg("Invalid number of options. Must be 2 or 3.")]
+    #[msg("Invalid number of options. Must be between 2 and MAX_OPTIONS.")]
     InvalidOptionsCount,
 
+    /// A single option exceeds the per-option byte limit
+    #[msg("An option exceeds the maximum allowed length.")]
+    OptionTooLong,
+
+    /// The question text exceeds the maximum byte limit
+    #[msg("The question exceeds the maximum allowed length.")]
+    QuestionTooLong,
+
     /// Selected option index is out of range
     #[msg("Selected option does not exist.")]
     InvalidOption,
+
+    /// Voter has no staked tokens to vote with
+    #[msg("Voter has no voting power.")]
+    NoVotingPower,
+
+    /// Token account's mint does not match the voting's governance mint
+    #[msg("Token account does not belong to the voting's governance mint.")]
+    WrongGovernanceMint,
+
+    /// Deposit lockup has not yet expired
+    #[msg("Deposit lockup has not expired yet.")]
+    LockupNotExpired,
+
+    /// Governance weight calculation overflowed
+    #[msg("Vote weight calculation overflowed.")]
+    WeightOverflow,
+
+    /// start_ts is after end_ts, so the window could never accept a vote
+    #[msg("start_ts must not be after end_ts.")]
+    InvalidVotingWindow,
+
+    /// Caller is not the voting authority
+    #[msg("Only the voting authority may perform this action.")]
+    Unauthorized,
+
+    /// Account data is malformed or too small to deserialize
+    #[msg("Voting account data is invalid.")]
+    InvalidAccount,
+
+    /// The voting window has not opened yet
+    #[msg("Voting has not started yet.")]
+    VotingNotStarted,
+
+    /// The voting window has closed
+    #[msg("Voting is closed.")]
+    VotingClosed,
+
+    /// Removing a ballot would underflow a vote counter
+    #[msg("Vote counter underflow.")]
+    VoteUnderflow,
 }